@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime configuration handed to a keep at `Backend::build` time.
+
+use crate::binary::Component;
+
+use std::path::PathBuf;
+
+/// A file descriptor the host pre-opens on the workload's behalf.
+pub struct FdConfig {
+    /// The fd number the workload will see this descriptor on.
+    pub fd: u32,
+
+    /// Where the descriptor's contents come from or go to.
+    pub source: FdSource,
+}
+
+/// The host-side origin of a pre-opened file descriptor.
+pub enum FdSource {
+    /// A regular file or special file (e.g. `/dev/null`) at this path.
+    Path(PathBuf),
+
+    /// A connected stream socket at this path.
+    Socket(PathBuf),
+}
+
+/// How verbosely the shim should log.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Default for LogLevel {
+    fn default() -> Self {
+        LogLevel::Off
+    }
+}
+
+/// Runtime configuration for a single keep instance.
+///
+/// Built by the caller and passed to `Backend::build`, which marshals it
+/// into the keep's initial `sallyport::Block` rather than baking any of
+/// this into the shim.
+pub struct KeepConfig {
+    /// The workload to run.
+    pub code: Component,
+
+    /// An optional debug socket the shim can be attached to.
+    pub debug_sock: Option<PathBuf>,
+
+    /// `argv` as seen by the workload.
+    pub args: Vec<String>,
+
+    /// Environment variables as seen by the workload.
+    pub env: Vec<(String, String)>,
+
+    /// File descriptors to pre-open inside the keep, keyed by the fd
+    /// number the workload expects them on (e.g. 0/1/2 for stdio).
+    pub fds: Vec<FdConfig>,
+
+    /// Shim log verbosity.
+    pub log_level: LogLevel,
+}
+
+impl KeepConfig {
+    /// Starts a configuration for `code` with no args, env, or fds.
+    pub fn new(code: Component) -> Self {
+        Self {
+            code,
+            debug_sock: None,
+            args: Vec::new(),
+            env: Vec::new(),
+            fds: Vec::new(),
+            log_level: LogLevel::default(),
+        }
+    }
+}
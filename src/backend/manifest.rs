@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! TOML launch manifests.
+//!
+//! A manifest names a backend, its resource limits, its args and env,
+//! and a `version` requirement checked against [`LOADER_VERSION`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// The version of this loader, used to check manifest compatibility.
+pub const LOADER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Resource limits requested for the keep.
+#[derive(Debug, Default, Deserialize)]
+pub struct Limits {
+    /// Maximum guest memory, in megabytes.
+    pub memory_mb: Option<u64>,
+
+    /// Maximum number of threads the workload may create.
+    pub threads: Option<u32>,
+}
+
+/// A parsed, version-checked launch manifest.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    /// The name of the backend to launch on (see `backend::get`).
+    pub backend: String,
+
+    /// A caret version requirement (e.g. `"^0.3"`) naming the loader and
+    /// shim versions this manifest was authored for.
+    pub version: String,
+
+    /// `argv` as seen by the workload.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Environment variables as seen by the workload.
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+
+    /// Resource limits requested for the keep.
+    #[serde(default)]
+    pub limits: Limits,
+}
+
+/// An error encountered while loading a launch manifest.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to read manifest {0}")]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error("failed to parse manifest {0}")]
+    Parse(PathBuf, #[source] toml::de::Error),
+
+    #[error("invalid version requirement {0:?}")]
+    InvalidVersionReq(String, #[source] semver::Error),
+
+    #[error("manifest version {0:?} is not a single caret requirement (expected e.g. \"^0.3\")")]
+    NotCaretRequirement(String),
+
+    #[error("manifest requires loader version {required}, but this is {running}")]
+    VersionMismatch { required: String, running: String },
+}
+
+/// Loads and validates the manifest at `path`.
+///
+/// Returns an error if the file can't be read, isn't valid TOML, or
+/// requires a loader version incompatible with [`LOADER_VERSION`].
+pub fn load(path: &Path) -> Result<Manifest, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    let manifest: Manifest =
+        toml::from_str(&text).map_err(|e| Error::Parse(path.to_path_buf(), e))?;
+
+    check_version(&manifest.version)?;
+
+    Ok(manifest)
+}
+
+/// Checks that `required`, a single caret comparator (e.g. `"^0.3"`),
+/// matches [`LOADER_VERSION`].
+fn check_version(required: &str) -> Result<(), Error> {
+    if !is_caret_requirement(required) {
+        return Err(Error::NotCaretRequirement(required.to_string()));
+    }
+
+    let req = VersionReq::parse(required)
+        .map_err(|e| Error::InvalidVersionReq(required.to_string(), e))?;
+    let running = Version::parse(LOADER_VERSION).expect("CARGO_PKG_VERSION is valid semver");
+
+    if !req.matches(&running) {
+        return Err(Error::VersionMismatch {
+            required: required.to_string(),
+            running: running.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether `required` is shaped like a single caret comparator (e.g.
+/// `"^0.3"`) rather than the full semver requirement grammar (e.g. a
+/// comma-separated comparator list).
+fn is_caret_requirement(required: &str) -> bool {
+    let required = required.trim();
+    required.starts_with('^') && !required.contains(',')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_matching_caret_requirement() {
+        let req = format!("^{}", LOADER_VERSION);
+        assert!(check_version(&req).is_ok());
+    }
+
+    #[test]
+    fn rejects_incompatible_version() {
+        let err = check_version("^99.0.0").unwrap_err();
+        assert!(matches!(err, Error::VersionMismatch { .. }));
+    }
+
+    #[test]
+    fn rejects_malformed_requirement() {
+        let err = check_version("^not-a-version").unwrap_err();
+        assert!(matches!(err, Error::InvalidVersionReq(_, _)));
+    }
+
+    #[test]
+    fn rejects_non_caret_requirement() {
+        let err = check_version(">=1.0, <2.0").unwrap_err();
+        assert!(matches!(err, Error::NotCaretRequirement(_)));
+    }
+}
@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `Keep` test double, used to unit test logic that dispatches
+//! through the `Keeps` enum without a real backend compiled in.
+
+use super::{secrets::SealedBundle, Keep, Threads};
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+/// A `Keep` that just records whether it was provisioned.
+#[derive(Default)]
+pub struct MockKeep {
+    pub provisioned: Cell<bool>,
+}
+
+impl Keep for MockKeep {
+    fn add_thread(self: Arc<Self>) -> Result<Box<Threads>> {
+        unimplemented!("not exercised by these tests")
+    }
+
+    fn provision(&self, _bundle: &SealedBundle) -> Result<()> {
+        self.provisioned.set(true);
+        Ok(())
+    }
+}
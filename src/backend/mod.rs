@@ -7,16 +7,53 @@ pub mod sev;
 #[cfg(feature = "backend-sgx")]
 pub mod sgx;
 
+mod config;
+pub mod manifest;
+#[cfg(test)]
+mod mock;
 mod probe;
+pub mod reactor;
+pub mod report;
+pub mod secrets;
+
+pub use config::{FdConfig, FdSource, KeepConfig, LogLevel};
 
-use crate::binary::Component;
 use crate::sallyport::Block;
 
-use std::path::Path;
+use std::os::unix::io::RawFd;
 use std::sync::Arc;
 
 use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use serde::Serialize;
+
+/// Looks up a compiled-in backend by name.
+///
+/// Returns `None` if no backend with the given name was compiled into
+/// this binary (i.e. its `backend-*` feature was disabled).
+pub fn get(name: &str) -> Option<Backends> {
+    all().into_iter().find(|backend| backend.name() == name)
+}
 
+/// Enumerates every backend compiled into this binary.
+///
+/// The set returned depends on which `backend-*` features were enabled
+/// at compile time; it says nothing about whether the current platform
+/// actually supports a given backend (see `Backend::have`).
+pub fn all() -> Vec<Backends> {
+    let mut backends = Vec::new();
+
+    #[cfg(feature = "backend-kvm")]
+    backends.push(kvm::Backend.into());
+    #[cfg(feature = "backend-sev")]
+    backends.push(sev::Backend.into());
+    #[cfg(feature = "backend-sgx")]
+    backends.push(sgx::Backend.into());
+
+    backends
+}
+
+#[enum_dispatch]
 pub trait Backend {
     /// The name of the backend
     fn name(&self) -> &'static str;
@@ -30,9 +67,29 @@ pub trait Backend {
     fn data(&self) -> Vec<Datum>;
 
     /// Create a keep instance on this backend
-    fn build(&self, code: Component, sock: Option<&Path>) -> Result<Arc<dyn Keep>>;
+    fn build(&self, config: &KeepConfig) -> Result<Arc<Keeps>>;
+
+    /// Returns the measurement/attestation for a keep previously
+    /// returned by `build` (see [`secrets::provision`]).
+    fn attest(&self, keep: &Keeps) -> Result<secrets::Attestation>;
 }
 
+/// The set of backends compiled into this binary.
+///
+/// Dispatch through this enum is static (a single match on the
+/// discriminant), unlike a `Box<dyn Backend>`, which pays for a vtable
+/// indirection on every call.
+#[enum_dispatch(Backend)]
+pub enum Backends {
+    #[cfg(feature = "backend-kvm")]
+    Kvm(kvm::Backend),
+    #[cfg(feature = "backend-sev")]
+    Sev(sev::Backend),
+    #[cfg(feature = "backend-sgx")]
+    Sgx(sgx::Backend),
+}
+
+#[derive(Serialize)]
 pub struct Datum {
     /// The name of this datum.
     pub name: String,
@@ -47,17 +104,74 @@ pub struct Datum {
     pub mesg: Option<String>,
 }
 
+#[enum_dispatch]
 pub trait Keep {
     /// Creates a new thread in the keep.
-    fn add_thread(self: Arc<Self>) -> Result<Box<dyn Thread>>;
+    fn add_thread(self: Arc<Self>) -> Result<Box<Threads>>;
+
+    /// Copies a sealed secret bundle into the keep for in-guest
+    /// unwrapping. Call only after verifying the keep's attestation
+    /// (see [`secrets::provision`]).
+    fn provision(&self, bundle: &secrets::SealedBundle) -> Result<()>;
 }
 
+/// The set of keep instances compiled into this binary.
+///
+/// Mirrors `Backends`: each backend's keep type is dispatched statically
+/// rather than through a `dyn Keep` trait object.
+#[enum_dispatch(Keep)]
+pub enum Keeps {
+    #[cfg(feature = "backend-kvm")]
+    Kvm(kvm::Keep),
+    #[cfg(feature = "backend-sev")]
+    Sev(sev::Keep),
+    #[cfg(feature = "backend-sgx")]
+    Sgx(sgx::Keep),
+    #[cfg(test)]
+    Mock(mock::MockKeep),
+}
+
+#[enum_dispatch]
 pub trait Thread {
     /// Enters the keep.
     fn enter(&mut self) -> Result<Command>;
 }
 
+/// The set of thread instances compiled into this binary.
+///
+/// The `enter()` loop is the hottest path in the loader (it runs once
+/// per guest syscall), so dispatching it statically through this enum
+/// rather than through `Box<dyn Thread>` avoids a vtable indirection on
+/// every call.
+#[enum_dispatch(Thread)]
+pub enum Threads {
+    #[cfg(feature = "backend-kvm")]
+    Kvm(kvm::Thread),
+    #[cfg(feature = "backend-sev")]
+    Sev(sev::Thread),
+    #[cfg(feature = "backend-sgx")]
+    Sgx(sgx::Thread),
+}
+
+/// What a thread did the last time it was entered: completed work, or
+/// parked until one of a set of fds is ready (see `reactor::Reactor`).
 pub enum Command<'a> {
     SysCall(&'a mut Block),
     Continue,
+    Park(Park),
+}
+
+/// A request to be re-entered once any of a set of host fds is ready.
+pub struct Park {
+    /// The fds the thread is blocked on.
+    pub fds: Vec<RawFd>,
+
+    /// What kind of readiness the thread is waiting for.
+    pub interest: Interest,
+}
+
+/// The kind of readiness a parked thread is waiting for.
+pub struct Interest {
+    pub readable: bool,
+    pub writable: bool,
 }
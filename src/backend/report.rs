@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine-readable probe reports.
+//!
+//! `Backend::data()` is meant for a human-readable pretty-print of
+//! platform support; `report` instead serializes the same information
+//! across every compiled-in backend, so CI and orchestration tooling
+//! can decide programmatically whether a node can host a given keep
+//! type.
+
+use super::{all, Backend, Datum};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// The probe results for a single compiled-in backend.
+#[derive(Serialize)]
+pub struct BackendReport {
+    /// The backend's name.
+    pub name: &'static str,
+
+    /// Whether the platform passes every datum for this backend.
+    pub have: bool,
+
+    /// The individual probe results.
+    pub data: Vec<Datum>,
+}
+
+/// The probe results for every compiled-in backend.
+#[derive(Serialize)]
+pub struct Report {
+    pub backends: Vec<BackendReport>,
+}
+
+/// Probes every compiled-in backend and returns the results as JSON.
+pub fn report() -> Result<String> {
+    let backends = all()
+        .into_iter()
+        .map(|backend| BackendReport {
+            name: backend.name(),
+            have: backend.have(),
+            data: backend.data(),
+        })
+        .collect();
+
+    Ok(serde_json::to_string(&Report { backends })?)
+}
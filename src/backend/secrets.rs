@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sealed secret provisioning.
+//!
+//! A [`SealedBundle`] is a symmetric-encrypted blob together with a
+//! KDF-wrapped content-encryption key, in the spirit of a KDBX-style
+//! container.
+
+use super::{Keep, Keeps};
+
+use anyhow::{bail, Result};
+
+/// An encrypted secret bundle plus the means to unwrap its key.
+pub struct SealedBundle {
+    /// Salt for the key-derivation function that produced the key
+    /// `wrapped_key` is wrapped under.
+    pub kdf_salt: Vec<u8>,
+
+    /// The content-encryption key, wrapped under the KDF-derived key.
+    pub wrapped_key: Vec<u8>,
+
+    /// Nonce for `ciphertext`.
+    pub nonce: Vec<u8>,
+
+    /// The encrypted secrets payload (e.g. a serialized name/value map).
+    pub ciphertext: Vec<u8>,
+}
+
+/// A measurement or attestation produced by a keep, used to decide
+/// whether it is trustworthy enough to receive sealed secrets.
+pub struct Attestation(pub Vec<u8>);
+
+/// Verifies `attestation` against `expected_measurement` and, if it
+/// matches, provisions `bundle` into `keep`. Unwrapping happens inside
+/// the keep itself, once the still-sealed bundle has landed in guest
+/// memory via the `sallyport::Block`.
+pub fn provision(
+    keep: &Keeps,
+    attestation: &Attestation,
+    expected_measurement: &[u8],
+    bundle: &SealedBundle,
+) -> Result<()> {
+    if attestation.0 != expected_measurement {
+        bail!("attestation does not match expected measurement; refusing to release secrets");
+    }
+
+    keep.provision(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockKeep;
+
+    fn bundle() -> SealedBundle {
+        SealedBundle {
+            kdf_salt: vec![],
+            wrapped_key: vec![],
+            nonce: vec![],
+            ciphertext: vec![],
+        }
+    }
+
+    #[test]
+    fn bails_on_measurement_mismatch() {
+        let keep = Keeps::Mock(MockKeep::default());
+        let attestation = Attestation(vec![1, 2, 3]);
+
+        assert!(provision(&keep, &attestation, &[9, 9, 9], &bundle()).is_err());
+
+        let Keeps::Mock(mock) = &keep else {
+            unreachable!()
+        };
+        assert!(!mock.provisioned.get());
+    }
+
+    #[test]
+    fn forwards_to_keep_provision_on_match() {
+        let keep = Keeps::Mock(MockKeep::default());
+        let attestation = Attestation(vec![1, 2, 3]);
+
+        provision(&keep, &attestation, &[1, 2, 3], &bundle()).unwrap();
+
+        let Keeps::Mock(mock) = &keep else {
+            unreachable!()
+        };
+        assert!(mock.provisioned.get());
+    }
+}